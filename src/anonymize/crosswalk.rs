@@ -0,0 +1,131 @@
+//! Pseudonym crosswalk for PatientID/PatientName, mirroring how clinical
+//! de-identification tools map patient identifiers to stable pseudonyms.
+//!
+//! Shared across every file processed in a single `anonymize_dicomdir` run
+//! (like [`super::UidMapper`]), and optionally persisted to an external file
+//! so the same real patient gets the same pseudonym across runs too.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::sync::Mutex;
+
+/// Maps original PatientID values to allocated pseudonyms.
+pub struct Crosswalk {
+    path: Option<String>,
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl Crosswalk {
+    /// Load a crosswalk from `path`. Starts empty if `path` is `None` or
+    /// does not exist yet; new allocations are only written back to disk
+    /// when `path` is `Some`.
+    pub fn load(path: Option<String>) -> io::Result<Self> {
+        let mut entries = HashMap::new();
+        if let Some(path) = &path {
+            match fs::read_to_string(path) {
+                Ok(contents) => {
+                    for line in contents.lines() {
+                        if let Some((original, pseudonym)) = line.split_once('\t') {
+                            entries.insert(original.to_string(), pseudonym.to_string());
+                        }
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Crosswalk {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Return the pseudonym mapped to `original_patient_id`, allocating and
+    /// persisting a new one the first time it is seen.
+    ///
+    /// The new allocation is only committed to `entries` once `persist`
+    /// succeeds, so a write failure can't leave an in-memory mapping that
+    /// was never durably saved - which would let later files in this run
+    /// reuse an unpersisted pseudonym instead of retrying the write.
+    pub fn pseudonym_for(&self, original_patient_id: &str) -> io::Result<String> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(existing) = entries.get(original_patient_id) {
+            return Ok(existing.clone());
+        }
+
+        let pseudonym = format!("PSEUDO{:08}", entries.len() + 1);
+
+        if let Some(path) = &self.path {
+            let mut candidate = entries.clone();
+            candidate.insert(original_patient_id.to_string(), pseudonym.clone());
+            Self::persist(path, &candidate)?;
+            *entries = candidate;
+        } else {
+            entries.insert(original_patient_id.to_string(), pseudonym.clone());
+        }
+
+        Ok(pseudonym)
+    }
+
+    fn persist(path: &str, entries: &HashMap<String, String>) -> io::Result<()> {
+        let mut contents = String::new();
+        for (original, pseudonym) in entries {
+            contents.push_str(original);
+            contents.push('\t');
+            contents.push_str(pseudonym);
+            contents.push('\n');
+        }
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pseudonym_for_is_stable_per_patient_and_differs_across_patients() {
+        let crosswalk = Crosswalk::load(None).unwrap();
+        let first = crosswalk.pseudonym_for("PATIENT-A").unwrap();
+        let repeat = crosswalk.pseudonym_for("PATIENT-A").unwrap();
+        let other = crosswalk.pseudonym_for("PATIENT-B").unwrap();
+
+        assert_eq!(first, repeat);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn load_round_trips_a_previously_persisted_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "pybrors-crosswalk-test-{:?}",
+            std::thread::current().id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("crosswalk.tsv").display().to_string();
+
+        let original = Crosswalk::load(Some(path.clone())).unwrap();
+        let allocated = original.pseudonym_for("PATIENT-A").unwrap();
+
+        let reloaded = Crosswalk::load(Some(path)).unwrap();
+        let reread = reloaded.pseudonym_for("PATIENT-A").unwrap();
+
+        assert_eq!(allocated, reread);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_persist_does_not_pollute_in_memory_entries() {
+        // A path under a directory that does not exist can never be
+        // written, so `persist` is guaranteed to fail here.
+        let path = "/nonexistent-dir/does-not-exist/crosswalk.tsv".to_string();
+        let crosswalk = Crosswalk::load(Some(path)).unwrap();
+
+        assert!(crosswalk.pseudonym_for("PATIENT-A").is_err());
+        // The failed allocation must not have been committed: a retry sees
+        // the same patient as still-unseen rather than reusing a pseudonym
+        // that was never durably saved.
+        assert!(crosswalk.entries.lock().unwrap().is_empty());
+    }
+}
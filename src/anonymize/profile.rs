@@ -0,0 +1,219 @@
+//! Built-in de-identification action tables, modeled on the Basic Application
+//! Level Confidentiality Profile defined in DICOM PS 3.15 Table E.1-1.
+//!
+//! Each revision of the standard has added a handful of attributes to the
+//! table, so profiles are versioned by the revision string that introduced
+//! them (e.g. `"2008"`, `"2017c"`, `"2021b"`) rather than hardcoded once.
+
+use dicom::core::Tag;
+use std::collections::HashMap;
+
+use super::tag_range::DicomTagRange;
+
+/// Action code applied to a single DICOM attribute, per PS 3.15 Table E.1-1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// `D` - replace with a non-zero-length dummy value.
+    Replace,
+    /// `Z` - replace with a zero-length value.
+    Empty,
+    /// `X` - remove the attribute entirely.
+    Remove,
+    /// `K` - keep the attribute unchanged.
+    Keep,
+    /// `U` - replace with a consistently remapped UID.
+    Uid,
+    /// `C` - clean: retain safe content, remove identifying content.
+    Clean,
+}
+
+/// A de-identification profile: the action to apply to each known tag, plus
+/// rules covering whole tag ranges (private tags, curve/overlay data, ...).
+///
+/// Tags matched by neither `actions` nor `ranges` default to [`Action::Keep`]
+/// when looked up through [`Profile::action_for`].
+pub struct Profile {
+    pub version: String,
+    actions: HashMap<Tag, Action>,
+    ranges: Vec<(DicomTagRange, Action)>,
+}
+
+impl Profile {
+    /// Action to apply to `tag` under this profile, defaulting to `Keep`.
+    ///
+    /// An explicit single-tag rule always wins over a range rule; among
+    /// range rules, the first one that matches applies.
+    pub fn action_for(&self, tag: Tag) -> Action {
+        if let Some(action) = self.actions.get(&tag).copied() {
+            return action;
+        }
+        self.ranges
+            .iter()
+            .find(|(range, _)| range.contains(tag))
+            .map(|(_, action)| *action)
+            .unwrap_or(Action::Keep)
+    }
+}
+
+/// Base table shared by every profile revision.
+fn base_table() -> Vec<(Tag, Action)> {
+    vec![
+        (Tag(0x0008, 0x0012), Action::Replace), // InstanceCreationDate
+        (Tag(0x0008, 0x0013), Action::Replace), // InstanceCreationTime
+        (Tag(0x0008, 0x0014), Action::Uid),     // InstanceCreatorUID
+        (Tag(0x0008, 0x0018), Action::Uid),     // SOPInstanceUID
+        (Tag(0x0008, 0x1155), Action::Uid),     // ReferencedSOPInstanceUID
+        (Tag(0x0008, 0x0020), Action::Replace), // StudyDate
+        (Tag(0x0008, 0x0021), Action::Replace), // SeriesDate
+        (Tag(0x0008, 0x0022), Action::Replace), // AcquisitionDate
+        (Tag(0x0008, 0x0023), Action::Replace), // ContentDate
+        (Tag(0x0008, 0x0030), Action::Replace), // StudyTime
+        (Tag(0x0008, 0x0050), Action::Empty),   // AccessionNumber
+        (Tag(0x0008, 0x0080), Action::Remove),  // InstitutionName
+        (Tag(0x0008, 0x0081), Action::Remove),  // InstitutionAddress
+        (Tag(0x0008, 0x0090), Action::Remove),  // ReferringPhysicianName
+        (Tag(0x0008, 0x0092), Action::Remove),  // ReferringPhysicianAddress
+        (Tag(0x0008, 0x0094), Action::Remove),  // ReferringPhysicianTelephoneNumbers
+        (Tag(0x0008, 0x1010), Action::Replace), // StationName
+        (Tag(0x0008, 0x1030), Action::Clean),   // StudyDescription
+        (Tag(0x0008, 0x103E), Action::Clean),   // SeriesDescription
+        (Tag(0x0008, 0x1040), Action::Remove),  // InstitutionalDepartmentName
+        (Tag(0x0008, 0x1048), Action::Remove),  // PhysiciansOfRecord
+        (Tag(0x0008, 0x1049), Action::Remove),  // PhysiciansOfRecordIdentificationSequence
+        (Tag(0x0008, 0x1050), Action::Remove),  // PerformingPhysicianName
+        (Tag(0x0008, 0x1060), Action::Remove),  // NameOfPhysiciansReadingStudy
+        (Tag(0x0008, 0x1070), Action::Remove),  // OperatorsName
+        (Tag(0x0008, 0x1080), Action::Remove),  // AdmittingDiagnosesDescription
+        (Tag(0x0010, 0x0010), Action::Replace), // PatientName
+        (Tag(0x0010, 0x0020), Action::Replace), // PatientID
+        (Tag(0x0010, 0x0030), Action::Replace), // PatientBirthDate
+        (Tag(0x0010, 0x0040), Action::Replace), // PatientSex
+        (Tag(0x0010, 0x1000), Action::Remove),  // OtherPatientIDs
+        (Tag(0x0010, 0x1001), Action::Remove),  // OtherPatientNames
+        (Tag(0x0010, 0x1010), Action::Remove),  // PatientAge
+        (Tag(0x0010, 0x1020), Action::Remove),  // PatientSize
+        (Tag(0x0010, 0x1030), Action::Remove),  // PatientWeight
+        (Tag(0x0010, 0x1090), Action::Remove),  // MedicalRecordLocator
+        (Tag(0x0010, 0x2160), Action::Remove),  // EthnicGroup
+        (Tag(0x0010, 0x2180), Action::Remove),  // Occupation
+        (Tag(0x0010, 0x21B0), Action::Remove),  // AdditionalPatientHistory
+        (Tag(0x0010, 0x4000), Action::Remove),  // PatientComments
+        (Tag(0x0018, 0x1000), Action::Replace), // DeviceSerialNumber
+        (Tag(0x0020, 0x000D), Action::Uid),     // StudyInstanceUID
+        (Tag(0x0020, 0x000E), Action::Uid),     // SeriesInstanceUID
+        (Tag(0x0020, 0x0010), Action::Replace), // StudyID
+        (Tag(0x0020, 0x0052), Action::Uid),     // FrameOfReferenceUID
+        (Tag(0x0032, 0x1032), Action::Remove),  // RequestingPhysician
+        (Tag(0x0032, 0x1033), Action::Remove),  // RequestingService
+        (Tag(0x0032, 0x1060), Action::Clean),   // RequestedProcedureDescription
+        (Tag(0x0040, 0x0006), Action::Remove),  // ScheduledPerformingPhysicianName
+        (Tag(0x0040, 0x0241), Action::Remove),  // PerformedStationAETitle
+        (Tag(0x0040, 0x0275), Action::Remove),  // RequestAttributesSequence
+        (Tag(0x0040, 0x1001), Action::Remove),  // RequestedProcedureID
+        (Tag(0x0040, 0x2004), Action::Remove),  // IssueDateOfImagingServiceRequest
+        (Tag(0x0040, 0xA730), Action::Remove),  // ContentSequence
+    ]
+}
+
+/// Attributes added to the table by the 2017c revision.
+fn additions_2017c() -> Vec<(Tag, Action)> {
+    vec![
+        (Tag(0x0018, 0x1002), Action::Uid), // DeviceUID
+        (Tag(0x0020, 0x0200), Action::Uid), // SynchronizationFrameOfReferenceUID
+        (Tag(0x0040, 0xA124), Action::Uid), // UID (content item)
+    ]
+}
+
+/// Attributes added to the table by the 2021b revision.
+fn additions_2021b() -> Vec<(Tag, Action)> {
+    vec![
+        (Tag(0x0008, 0x010D), Action::Uid), // ContextGroupExtensionCreatorUID
+        (Tag(0x0018, 0x4000), Action::Clean), // AcquisitionComments
+    ]
+}
+
+/// Range rules shared by every profile revision: bulk removal of private
+/// tags and of the retired curve/overlay data groups.
+fn base_ranges() -> Vec<(DicomTagRange, Action)> {
+    vec![
+        (DicomTagRange::private_tags(), Action::Remove),
+        // Curve Data (retired)
+        (
+            DicomTagRange::new(0x5000, 0x50FF, 0x0000, 0xFFFF),
+            Action::Remove,
+        ),
+        // Overlay Data / Overlay Comments (retired)
+        (
+            DicomTagRange::new(0x6000, 0x60FF, 0x0000, 0xFFFF),
+            Action::Remove,
+        ),
+    ]
+}
+
+/// Build the built-in [`Profile`] for a given standard revision, or `None`
+/// if `version` names no revision this crate knows about.
+///
+/// This is the single source of truth for which revisions are supported -
+/// callers must not keep a separate list of valid version strings, since
+/// that list would have to be updated by hand every time a revision is
+/// added here.
+pub fn built_in_profile(version: &str) -> Option<Profile> {
+    let mut actions: HashMap<Tag, Action> = base_table().into_iter().collect();
+
+    match version {
+        "2008" => {}
+        "2017c" => actions.extend(additions_2017c()),
+        "2021b" => {
+            actions.extend(additions_2017c());
+            actions.extend(additions_2021b());
+        }
+        _ => return None,
+    }
+
+    Some(Profile {
+        version: version.to_string(),
+        actions,
+        ranges: base_ranges(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_for_defaults_to_keep() {
+        let profile = Profile {
+            version: "test".to_string(),
+            actions: HashMap::new(),
+            ranges: Vec::new(),
+        };
+        assert_eq!(profile.action_for(Tag(0x0009, 0x0010)), Action::Keep);
+    }
+
+    #[test]
+    fn action_for_prefers_single_tag_over_matching_range() {
+        let tag = Tag(0x0009, 0x0010);
+        let mut actions = HashMap::new();
+        actions.insert(tag, Action::Replace);
+        let profile = Profile {
+            version: "test".to_string(),
+            actions,
+            ranges: vec![(DicomTagRange::private_tags(), Action::Remove)],
+        };
+        // The range also matches this tag, but the explicit single-tag rule
+        // must win.
+        assert_eq!(profile.action_for(tag), Action::Replace);
+    }
+
+    #[test]
+    fn action_for_falls_back_to_first_matching_range() {
+        let tag = Tag(0x0009, 0x0010);
+        let profile = Profile {
+            version: "test".to_string(),
+            actions: HashMap::new(),
+            ranges: vec![(DicomTagRange::private_tags(), Action::Remove)],
+        };
+        assert_eq!(profile.action_for(tag), Action::Remove);
+    }
+}
@@ -0,0 +1,24 @@
+//! Per-file anonymization outcome, surfaced to Python as a list of dicts so a
+//! handful of malformed files don't abort an entire directory.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Outcome of anonymizing a single file.
+pub struct FileReport {
+    pub source: String,
+    pub success: bool,
+    pub output: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl IntoPy<PyObject> for FileReport {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        let dict = PyDict::new(py);
+        dict.set_item("source", self.source).unwrap();
+        dict.set_item("success", self.success).unwrap();
+        dict.set_item("output", self.output).unwrap();
+        dict.set_item("reason", self.reason).unwrap();
+        dict.into()
+    }
+}
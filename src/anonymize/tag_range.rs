@@ -0,0 +1,96 @@
+//! Tag ranges, so a profile rule can cover a whole family of tags (private
+//! tags, curve/overlay data, ...) instead of enumerating every tag in it.
+
+use dicom::core::Tag;
+
+/// An inclusive range of DICOM tags, bounded independently on group and
+/// element.
+#[derive(Debug, Clone, Copy)]
+pub struct DicomTagRange {
+    pub group_from: u16,
+    pub group_to: u16,
+    pub element_from: u16,
+    pub element_to: u16,
+    /// Restrict the match to odd-numbered groups. Private tags always sit
+    /// in an odd group, and odd/even groups are interleaved rather than
+    /// contiguous, so a plain group bound can't express "every private
+    /// tag" on its own.
+    odd_groups_only: bool,
+}
+
+impl DicomTagRange {
+    pub fn new(group_from: u16, group_to: u16, element_from: u16, element_to: u16) -> Self {
+        DicomTagRange {
+            group_from,
+            group_to,
+            element_from,
+            element_to,
+            odd_groups_only: false,
+        }
+    }
+
+    /// Range covering every private tag (odd group, any element).
+    ///
+    /// Per PS 3.5, private group numbers run `0x0009..0xFFFD` - `0xFFFF` is
+    /// reserved and not a valid private (or any) group, so it must stay
+    /// outside this range.
+    pub fn private_tags() -> Self {
+        DicomTagRange {
+            group_from: 0x0009,
+            group_to: 0xFFFD,
+            element_from: 0x0000,
+            element_to: 0xFFFF,
+            odd_groups_only: true,
+        }
+    }
+
+    pub fn contains(&self, tag: Tag) -> bool {
+        let Tag(group, element) = tag;
+        if group < self.group_from || group > self.group_to {
+            return false;
+        }
+        if element < self.element_from || element > self.element_to {
+            return false;
+        }
+        if self.odd_groups_only && group % 2 == 0 {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_respects_group_bounds() {
+        let range = DicomTagRange::new(0x5000, 0x50FF, 0x0000, 0xFFFF);
+        assert!(!range.contains(Tag(0x4FFF, 0x0000)));
+        assert!(range.contains(Tag(0x5000, 0x0000)));
+        assert!(range.contains(Tag(0x50FF, 0x0000)));
+        assert!(!range.contains(Tag(0x5100, 0x0000)));
+    }
+
+    #[test]
+    fn contains_respects_element_bounds() {
+        let range = DicomTagRange::new(0x0009, 0x0009, 0x0010, 0x0020);
+        assert!(!range.contains(Tag(0x0009, 0x000F)));
+        assert!(range.contains(Tag(0x0009, 0x0010)));
+        assert!(range.contains(Tag(0x0009, 0x0020)));
+        assert!(!range.contains(Tag(0x0009, 0x0021)));
+    }
+
+    #[test]
+    fn private_tags_matches_only_odd_groups() {
+        let range = DicomTagRange::private_tags();
+        assert!(range.contains(Tag(0x0009, 0x0010)));
+        assert!(!range.contains(Tag(0x0008, 0x0010)));
+    }
+
+    #[test]
+    fn private_tags_excludes_reserved_group_ffff() {
+        let range = DicomTagRange::private_tags();
+        assert!(!range.contains(Tag(0xFFFF, 0x0000)));
+    }
+}
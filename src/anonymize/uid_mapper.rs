@@ -0,0 +1,97 @@
+//! Consistent, relationship-preserving UID remapping shared across every
+//! file processed in a single `anonymize_dicomdir` run.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// Default UID root used to prefix every generated UID. Not registered with
+/// an authority; swap for a site-specific root if one is assigned.
+const DEFAULT_ORG_ROOT: &str = "1.2.826.0.1.3680043.10.188";
+
+/// Maps original UIDs to newly generated ones, reusing the same mapping for
+/// every subsequent occurrence so that study/series/instance relationships
+/// survive anonymization.
+///
+/// Shared behind a [`Mutex`] across the parallel iteration in
+/// `anonymize_dicomdir`, so every file sees the same mapping.
+pub struct UidMapper {
+    root: String,
+    map: Mutex<HashMap<String, String>>,
+}
+
+impl UidMapper {
+    pub fn new() -> Self {
+        Self::with_root(DEFAULT_ORG_ROOT)
+    }
+
+    pub fn with_root(root: &str) -> Self {
+        UidMapper {
+            root: root.to_string(),
+            map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the UID mapped to `original`, generating and recording one the
+    /// first time `original` is seen.
+    ///
+    /// The generated UID is `root` concatenated with a hash of `original`
+    /// reduced to decimal digits, so it is valid (digits and dots only) and
+    /// deterministic for a given `root`.
+    pub fn remap(&self, original: &str) -> String {
+        if original.is_empty() {
+            return original.to_string();
+        }
+
+        let mut map = self.map.lock().unwrap();
+        if let Some(existing) = map.get(original) {
+            return existing.clone();
+        }
+
+        let mut hasher = DefaultHasher::new();
+        original.hash(&mut hasher);
+        let new_uid = format!("{}.{}", self.root, hasher.finish());
+        map.insert(original.to_string(), new_uid.clone());
+        new_uid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remap_is_stable_for_the_same_input() {
+        let mapper = UidMapper::new();
+        let first = mapper.remap("1.2.840.10008.1");
+        let second = mapper.remap("1.2.840.10008.1");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn remap_differs_across_inputs() {
+        let mapper = UidMapper::new();
+        let a = mapper.remap("1.2.840.10008.1");
+        let b = mapper.remap("1.2.840.10008.2");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn remap_passes_through_the_empty_string() {
+        let mapper = UidMapper::new();
+        assert_eq!(mapper.remap(""), "");
+    }
+
+    #[test]
+    fn remap_produces_a_valid_uid() {
+        let mapper = UidMapper::with_root("1.2.3");
+        let uid = mapper.remap("original-value");
+        assert!(
+            uid.chars().all(|c| c.is_ascii_digit() || c == '.'),
+            "generated UID must contain only digits and dots, got {}",
+            uid
+        );
+        assert!(uid.starts_with("1.2.3."));
+    }
+}
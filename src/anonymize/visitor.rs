@@ -0,0 +1,139 @@
+//! Recursive application of a [`Profile`] to elements nested inside DICOM
+//! sequences, at arbitrary nesting depth.
+//!
+//! `anonymize_file` only used to inspect top-level elements, so identifying
+//! tags buried inside a sequence item (e.g. `ReferencedSOPInstanceUID`
+//! inside `ReferencedImageSequence`) were left untouched. This walks every
+//! `SQ` element, descends into each item's dataset, applies the same action
+//! table, and writes the modified items back.
+
+use dicom::core::header::Header;
+use dicom::core::value::{DataSetSequence, Value};
+use dicom::core::{DataElement, Tag, VR};
+use dicom::object::InMemDicomObject;
+
+use super::{apply_action, AnonymizationContext, Profile};
+
+/// Apply `profile` to every element actually present in `obj` - covering
+/// both single-tag rules and range rules (private tags, curve/overlay
+/// data, ...) - then recurse into sequences.
+pub(crate) fn apply_profile(
+    obj: &mut InMemDicomObject,
+    profile: &Profile,
+    ctx: &AnonymizationContext,
+) {
+    let present_tags: Vec<Tag> = obj.iter().map(|element| element.header().tag).collect();
+    for tag in present_tags {
+        apply_action(obj, tag, profile.action_for(tag), ctx);
+    }
+    visit_sequences(obj, profile, ctx);
+}
+
+/// Find every `SQ` element still present in `obj`, and apply `profile` to
+/// each item's dataset, recursing into sequences nested within those items.
+fn visit_sequences(obj: &mut InMemDicomObject, profile: &Profile, ctx: &AnonymizationContext) {
+    let sequence_tags: Vec<Tag> = obj
+        .iter()
+        .filter(|element| element.header().vr() == VR::SQ)
+        .map(|element| element.header().tag)
+        .collect();
+
+    for tag in sequence_tags {
+        let Ok(element) = obj.element(tag) else {
+            continue;
+        };
+        let Value::Sequence(sequence) = element.value() else {
+            continue;
+        };
+
+        let mut items: Vec<InMemDicomObject> = sequence.items().to_vec();
+        for item in items.iter_mut() {
+            apply_profile(item, profile, ctx);
+        }
+
+        let new_sequence = DataSetSequence::from((items, sequence.length()));
+        obj.put_element(DataElement::new(tag, VR::SQ, Value::Sequence(new_sequence)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anonymize::{built_in_profile, DerivedValues, UidMapper};
+    use dicom::core::header::Length;
+    use dicom::core::PrimitiveValue;
+
+    fn test_context(uid_mapper: &UidMapper) -> AnonymizationContext {
+        AnonymizationContext {
+            derived: DerivedValues {
+                new_pid: "PSEUDO00000001".to_string(),
+                new_station: "ANON-STATION".to_string(),
+                new_study_date: "19000101".to_string(),
+                new_birth_date: "19000101".to_string(),
+                study_uid: "1.2.3.4".to_string(),
+            },
+            uid_mapper,
+        }
+    }
+
+    #[test]
+    fn apply_profile_recurses_into_nested_sequence_items() {
+        // ReferencedSOPInstanceUID, nested inside ReferencedImageSequence,
+        // is one of the tags that motivated this module: a plain top-level
+        // sweep never sees it.
+        let referenced_sequence_tag = Tag(0x0008, 0x1140); // ReferencedImageSequence
+        let referenced_uid_tag = Tag(0x0008, 0x1155); // ReferencedSOPInstanceUID
+        let sibling_tag = Tag(0x0008, 0x0060); // Modality, untouched by the profile
+
+        let mut item = InMemDicomObject::new_empty();
+        item.put_element(DataElement::new(
+            referenced_uid_tag,
+            VR::UI,
+            Value::from(PrimitiveValue::from("1.2.840.10008.original".to_string())),
+        ));
+        item.put_element(DataElement::new(
+            sibling_tag,
+            VR::CS,
+            Value::from(PrimitiveValue::from("CT".to_string())),
+        ));
+
+        let mut obj = InMemDicomObject::new_empty();
+        let sequence = DataSetSequence::from((vec![item], Length::UNDEFINED));
+        obj.put_element(DataElement::new(
+            referenced_sequence_tag,
+            VR::SQ,
+            Value::Sequence(sequence),
+        ));
+
+        let profile = built_in_profile("2021b").expect("2021b is a known profile version");
+        let uid_mapper = UidMapper::new();
+        let ctx = test_context(&uid_mapper);
+
+        apply_profile(&mut obj, &profile, &ctx);
+
+        let Value::Sequence(sequence) = obj.element(referenced_sequence_tag).unwrap().value()
+        else {
+            panic!("expected ReferencedImageSequence to still be a sequence");
+        };
+        let item = &sequence.items()[0];
+
+        let remapped = item
+            .element(referenced_uid_tag)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_ne!(remapped, "1.2.840.10008.original");
+        assert_eq!(
+            remapped,
+            uid_mapper.remap("1.2.840.10008.original"),
+            "same original UID must remap to the same value both times"
+        );
+
+        let sibling = item.element(sibling_tag).unwrap().to_str().unwrap();
+        assert_eq!(
+            sibling, "CT",
+            "untouched sibling element must survive the round-trip"
+        );
+    }
+}